@@ -0,0 +1,102 @@
+//! Configuration for how a [`Trace::leaf`](crate::Trace::leaf) capture is performed.
+
+use std::{env, sync::OnceLock};
+
+/// Controls a single capture: whether it's enabled at all, how deep it unwinds, and which frames
+/// are dropped before they ever reach the tree.
+///
+/// The default, used by [`Trace::capture`](crate::Trace::capture), is read once from the
+/// `SCOPED_TRACE` environment variable — mirroring [`std::backtrace::Backtrace`]'s
+/// `RUST_BACKTRACE`/`RUST_LIB_BACKTRACE` handling — and cached for the life of the process: set it
+/// to `0` or `off` to disable capture entirely, or leave it unset (or set to anything else) to
+/// capture with no limits. Use [`Trace::capture_with`](crate::Trace::capture_with) to override
+/// this on a per-capture basis.
+#[derive(Clone, Debug)]
+pub struct CaptureOptions {
+    enabled: bool,
+    max_depth: Option<usize>,
+    exclude: Vec<String>,
+}
+
+impl CaptureOptions {
+    /// A capture that's fully enabled: unwinds to any depth and drops no frames.
+    pub const fn new() -> Self {
+        Self {
+            enabled: true,
+            max_depth: None,
+            exclude: Vec::new(),
+        }
+    }
+
+    /// A capture that does nothing: [`Trace::leaf`](crate::Trace::leaf) becomes a near-zero-cost
+    /// no-op, returning before it allocates anything.
+    pub const fn disabled() -> Self {
+        Self {
+            enabled: false,
+            max_depth: None,
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Stop unwinding once `depth` frames above [`Trace::leaf`](crate::Trace::leaf) have been
+    /// captured, rather than continuing all the way to [`Trace::root`](crate::Trace::root).
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Drop any frame whose resolved name starts with `prefix` (e.g. `"tokio::"`) before it's
+    /// added to the trace. May be called more than once to add several prefixes.
+    pub fn exclude(mut self, prefix: impl Into<String>) -> Self {
+        self.exclude.push(prefix.into());
+        self
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Whether `frame` should be dropped, per [`CaptureOptions::exclude`].
+    ///
+    /// Resolves `frame` in place, so a kept frame's name is already cached on it by the time it's
+    /// pushed into the trace — [`Tree::from_trace`](crate::tree::Tree::from_trace) resolving it
+    /// again later is then a no-op, rather than paying resolution twice for every kept frame. When
+    /// [`CaptureOptions::exclude`] has never been called, this returns before resolving anything.
+    pub(crate) fn excludes(&self, frame: &mut backtrace::BacktraceFrame) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+
+        frame.resolve();
+        frame.symbols().iter().any(|symbol| {
+            symbol.name().is_some_and(|name| {
+                let name = name.to_string();
+                self.exclude
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix.as_str()))
+            })
+        })
+    }
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The process-wide default [`CaptureOptions`], computed once from the `SCOPED_TRACE`
+/// environment variable and cached for the life of the process.
+pub(crate) fn default_options() -> &'static CaptureOptions {
+    static DEFAULT: OnceLock<CaptureOptions> = OnceLock::new();
+    DEFAULT.get_or_init(|| match env::var("SCOPED_TRACE") {
+        Ok(value) if value == "0" || value.eq_ignore_ascii_case("off") => {
+            CaptureOptions::disabled()
+        }
+        _ => CaptureOptions::new(),
+    })
+}
@@ -0,0 +1,91 @@
+//! Attaching a [`Trace`] to an error at the point it's constructed.
+
+use std::fmt;
+
+use crate::{capture_here, CaptureOptions, RawTrace, Trace};
+
+/// Wraps an error together with a [`Trace`] captured when it was constructed, rendering the trace
+/// beneath the error's own [`Display`](fmt::Display) — in the same spirit as the causal
+/// backtraces that context crates like `anyhow`/`eyre` attach to the errors they wrap.
+///
+/// Construct one directly with [`TracedError::new`]/[`TracedError::with_options`], or via the
+/// [`TraceError`] extension methods on any [`std::error::Error`].
+pub struct TracedError<E> {
+    error: E,
+    trace: Trace,
+}
+
+impl<E> TracedError<E> {
+    /// Wrap `error`, capturing a [`Trace`] with the process-wide default [`CaptureOptions`].
+    pub fn new(error: E) -> Self {
+        Self::with_options(error, crate::capture::default_options().clone())
+    }
+
+    /// Like [`TracedError::new`], but with an explicit [`CaptureOptions`] rather than the
+    /// process-wide default.
+    pub fn with_options(error: E, options: CaptureOptions) -> Self {
+        let trace = Trace {
+            backtraces: vec![RawTrace::Unwound(capture_here(&options))],
+        };
+        Self { error, trace }
+    }
+
+    /// The trace captured when this error was constructed.
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// The wrapped error.
+    pub fn inner(&self) -> &E {
+        &self.error
+    }
+
+    /// Unwraps this, discarding the captured trace.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TracedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.error)?;
+        write!(f, "{}", self.trace)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for TracedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracedError")
+            .field("error", &self.error)
+            .field("trace", &format_args!("{}", self.trace))
+            .finish()
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for TracedError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// Extension trait adding [`Trace`] capture to any [`std::error::Error`] — see [`TracedError`].
+pub trait TraceError: std::error::Error + Sized {
+    /// Wrap `self` in a [`TracedError`], capturing a [`Trace`] with the process-wide default
+    /// [`CaptureOptions`].
+    ///
+    /// Named `traced` rather than `trace` so it can't collide with
+    /// [`TracedError::trace`](TracedError::trace) — `TracedError<E>` is itself an
+    /// [`std::error::Error`], so this extension method applies to it too, and a by-value trait
+    /// method of the same name as a by-ref inherent one would silently win method resolution.
+    fn traced(self) -> TracedError<Self> {
+        TracedError::new(self)
+    }
+
+    /// Like [`TraceError::traced`], but with an explicit [`CaptureOptions`] rather than the
+    /// process-wide default.
+    fn with_trace(self, options: CaptureOptions) -> TracedError<Self> {
+        TracedError::with_options(self, options)
+    }
+}
+
+impl<E: std::error::Error> TraceError for E {}
@@ -0,0 +1,138 @@
+//! Tracing support for asynchronous code.
+//!
+//! [`Trace::leaf`](crate::Trace::leaf) only works when [`Trace::root`](crate::Trace::root) is on
+//! the live call stack, which isn't the case for a task parked mid-`.await`: by the time anyone
+//! asks for a trace, the stack that suspended the task is long gone. [`Traced`] and
+//! [`trace_leaf`] extend the same root/leaf model to futures: wrapping a future in [`Traced`]
+//! re-establishes the root on every [`poll`](std::future::Future::poll), so that [`trace_leaf`],
+//! called from anywhere within it (including from other futures it polls), can record exactly
+//! where that task is currently parked.
+
+use std::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use crate::{capture_leaf, Context, Frame, Trace};
+
+/// Records a backtrace at this point in a hand-written [`Future::poll`] implementation, if a
+/// trace is currently active.
+///
+/// Call this at the top of `poll`/`poll_read`/etc. via `ready!(trace_leaf(cx))`: when no trace is
+/// active (the task isn't being polled beneath a [`Trace::capture_future`] or
+/// [`Trace::capture`]) it returns `Poll::Ready(())` immediately and cheaply, so the rest of `poll`
+/// runs as normal. When a trace *is* active, it behaves like [`Trace::leaf`], capturing a
+/// backtrace from here up to the nearest enclosing [`Traced`] or [`Trace::root`] before likewise
+/// returning `Poll::Ready(())` — the recorded
+/// leaf is the caller's own frame, not `trace_leaf`'s.
+#[inline(never)]
+pub fn trace_leaf(_cx: &mut task::Context<'_>) -> Poll<()> {
+    // SAFETY: `trace_leaf` is the function currently executing.
+    unsafe { capture_leaf(trace_leaf as *const _) };
+    Poll::Ready(())
+}
+
+/// A [`Future`] that re-establishes itself as a [`Trace::root`]-like boundary on every poll.
+///
+/// Constructed by [`Trace::root_future`] and, indirectly, by [`Trace::capture_future`]. Its
+/// [`Frame`] is registered once, on the first poll, and stays registered in the live tree for as
+/// long as this `Traced` exists — including while the task it wraps is parked mid-`.await` between
+/// polls — so [`Trace::snapshot_active`](crate::Trace::snapshot_active) can observe it from
+/// outside, not just from within an active `poll` call.
+///
+/// `Traced` is always `!Unpin`, even when `F: Unpin` — its `Frame` is registered by address on the
+/// first poll, so moving a `Traced` after that would leave a dangling pointer in the live tree.
+/// Without this, a caller could pin-project around an `Unpin` inner future, move the `Traced` once
+/// pinned, and poll again.
+pub struct Traced<F> {
+    future: F,
+    frame: UnsafeCell<Option<Frame>>,
+    _pin: std::marker::PhantomPinned,
+}
+
+impl<F> Traced<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self {
+            future,
+            frame: UnsafeCell::new(None),
+            _pin: std::marker::PhantomPinned,
+        }
+    }
+}
+
+impl<F: Future> Future for Traced<F> {
+    type Output = F::Output;
+
+    #[inline(never)]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        unsafe {
+            let this = self.get_unchecked_mut();
+            let future = Pin::new_unchecked(&mut this.future);
+
+            let frame_slot = &mut *this.frame.get();
+            if frame_slot.is_none() {
+                let mut frame = Frame {
+                    inner_addr: <Traced<F> as Future>::poll as *const c_void,
+                    parent: None,
+                    children: UnsafeCell::new(Vec::new()),
+                };
+                Context::with_current_frame(|current| {
+                    frame.parent = current.get();
+                });
+                // Place the frame at its final address before registering it — `register` stores
+                // a pointer to it, which must not dangle the moment this slot is written.
+                *frame_slot = Some(frame);
+                frame_slot.as_ref().unwrap().register();
+            }
+            let frame = frame_slot.as_ref().unwrap();
+
+            let previous = Context::with_current_frame(|current| {
+                current.replace(Some(std::ptr::NonNull::from(&*frame)))
+            });
+
+            let _restore = crate::defer(|| {
+                Context::with_current_frame(|current| {
+                    current.set(previous);
+                });
+            });
+
+            future.poll(cx)
+        }
+    }
+}
+
+/// A [`Future`] that collects a [`Trace`] over the course of polling `future`, as produced by
+/// [`Trace::capture_future`].
+pub struct CaptureFuture<F> {
+    pub(crate) traced: Traced<F>,
+    pub(crate) trace: Trace,
+}
+
+impl<F: Future> Future for CaptureFuture<F> {
+    type Output = (F::Output, Trace);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        unsafe {
+            let this = self.get_unchecked_mut();
+            let traced = Pin::new_unchecked(&mut this.traced);
+
+            let collector = Trace { backtraces: vec![] };
+            let previous =
+                Context::with_current_collector(|current| current.replace(Some(collector)));
+
+            let poll = traced.poll(cx);
+
+            let collector = Context::with_current_collector(|current| current.replace(previous))
+                .unwrap();
+            this.trace.backtraces.extend(collector.backtraces);
+
+            poll.map(|output| {
+                let trace = std::mem::replace(&mut this.trace, Trace { backtraces: vec![] });
+                (output, trace)
+            })
+        }
+    }
+}
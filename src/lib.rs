@@ -14,7 +14,7 @@
 //! use scoped_trace::Trace;
 //!
 //! fn main() {
-//!     let (_, trace) = Trace::root(|| foo());
+//!     let (_, trace) = Trace::capture(|| foo());
 //!     println!("{trace}");
 //! }
 //!
@@ -39,26 +39,148 @@
 //!   └╼ inlining::foo at example.rs:10:5
 //!      └╼ inlining::baz at example.rs:18:5
 //! ```
+//!
+//! [`Trace::leaf`] only works when [`Trace::root`] is on the live call stack, so it can't trace a
+//! task parked mid-`.await`. [`Trace::root_future`]/[`Trace::capture_future`] and [`trace_leaf`]
+//! extend the same model to async code: wrap a future so that it re-establishes the root on every
+//! poll, and call `ready!(trace_leaf(cx))` at the top of any hand-written `poll` to record exactly
+//! where the task is currently parked.
+//!
+//! [`Trace::snapshot_active`] takes a different tack: instead of unwinding a stack, it walks a
+//! live tree of every [`Trace::root`]/[`Trace::root_future`] invocation currently active on any
+//! thread, so a trace can be read from *outside* the traced execution entirely — a signal handler
+//! or an admin endpoint, say — without any of its leaves needing to be on an unwinding stack. The
+//! tradeoff is resolution: it records one frame per live root, not every intervening stack frame.
+//!
+//! Capture cost and verbosity are controlled by [`CaptureOptions`], in the same spirit as
+//! [`std::backtrace::Backtrace`]'s `RUST_BACKTRACE` handling: [`Trace::capture`] picks up a
+//! process-wide default from the `SCOPED_TRACE` environment variable, and [`Trace::capture_with`]
+//! overrides it explicitly to disable capture, limit unwinding depth, or exclude frames by name
+//! prefix.
+//!
+//! [`TracedError`] attaches a [`Trace`] to an error at the point it's constructed, rendering it
+//! beneath the error's own [`Display`](std::fmt::Display) — see its docs, and the [`TraceError`]
+//! extension trait, for the error-handling side of this crate. Symbol resolution for any trace is
+//! deferred until it's walked or formatted; call [`Trace::resolve`] to force it earlier, before a
+//! trace outlives the debug info it was captured with — for example, before sending it across a
+//! thread or serializing it.
 
 use backtrace::BacktraceFrame;
-use std::{cell::Cell, ffi::c_void, fmt, ptr::{self, NonNull}};
+use std::{
+    cell::{Cell, UnsafeCell},
+    ffi::c_void,
+    fmt,
+    ptr::{self, NonNull},
+    sync::Mutex,
+};
 
+mod capture;
+mod error;
+mod future;
 mod symbol;
 mod tree;
 
+pub use capture::CaptureOptions;
+use capture::default_options;
+pub use error::{TraceError, TracedError};
+pub use future::{trace_leaf, CaptureFuture, Traced};
 use symbol::Symbol;
-use tree::Tree;
+pub use tree::{Node, TraceTree};
+use tree::{symbolize, Tree};
 
 type Backtrace = Vec<BacktraceFrame>;
 type SymbolTrace = Vec<Symbol>;
 
+/// A captured, not-yet-symbolized backtrace.
+///
+/// Either a full unwound stack, captured by [`Trace::leaf`], or a coarser chain of addresses read
+/// directly from the live [`Frame`] tree by [`Trace::snapshot_active`]. Either kind pays its
+/// symbol-resolution cost lazily, each time it's walked or formatted (walking and formatting
+/// resolve a copy, so the result isn't kept) — unless it's already been forced via
+/// [`Trace::resolve`], in which case it's carried as `Resolved`.
+#[derive(Clone)]
+enum RawTrace {
+    Unwound(Backtrace),
+    Live(Vec<*const c_void>),
+    Resolved(SymbolTrace),
+}
+
 /// A [`Frame`] in an intrusive, doubly-linked tree of [`Frame`]s.
+///
+/// Every live [`Trace::root`] invocation (and every [`Traced`] future, from its first poll until
+/// it's dropped) on any thread registers one of these as a child of its parent — or, if it has none, as a new root of [`LIVE_TREE`] — and
+/// unlinks it again on `Drop`, so [`Trace::snapshot_active`] can walk the whole forest of
+/// in-flight traces without unwinding anything.
 pub(crate) struct Frame {
     // The location associated with this frame.
-    inner_addr: *const c_void,
+    pub(crate) inner_addr: *const c_void,
 
     // The kind of this frame — either a root or a node.
-    parent: Option<NonNull<Frame>>,
+    pub(crate) parent: Option<NonNull<Frame>>,
+
+    // The frames of any nested `Trace::root`/`Traced` invocations currently live beneath this
+    // one. Guarded by `LIVE_TREE`'s lock, not by any per-frame synchronization.
+    pub(crate) children: UnsafeCell<Vec<SendPtr>>,
+}
+
+// SAFETY: every access to a `Frame` reachable from `LIVE_TREE` — including from another thread —
+// happens while holding `LIVE_TREE`'s lock, and a `Frame` unlinks itself from that tree before
+// it's dropped.
+unsafe impl Send for Frame {}
+unsafe impl Sync for Frame {}
+
+/// A `NonNull<Frame>` that's safe to hold in [`LIVE_TREE`] across threads.
+///
+/// `NonNull` is never `Send`/`Sync`, no matter what it points to — it mirrors a raw pointer's
+/// default of "ownership unknown", regardless of the fact that [`Frame`] itself is. Wrapping it in
+/// a distinct type lets us assert the same guarantee [`Frame`]'s own `unsafe impl`s already rely
+/// on: every access happens while holding `LIVE_TREE`'s lock.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct SendPtr(NonNull<Frame>);
+
+// SAFETY: see `Frame`'s own `unsafe impl Send`/`Sync` above.
+unsafe impl Send for SendPtr {}
+
+impl From<&Frame> for SendPtr {
+    fn from(frame: &Frame) -> Self {
+        SendPtr(NonNull::from(frame))
+    }
+}
+
+/// The root [`Frame`]s of every [`Trace::root`]/[`Traced`] invocation currently live on any
+/// thread — the forest that [`Trace::snapshot_active`] walks.
+static LIVE_TREE: Mutex<Vec<SendPtr>> = Mutex::new(Vec::new());
+
+impl Frame {
+    /// Registers this frame as a child of its parent, or, if it has none, as a new root of
+    /// [`LIVE_TREE`].
+    unsafe fn register(&self) {
+        let mut roots = LIVE_TREE.lock().unwrap();
+        match self.parent {
+            Some(parent) => (*parent.as_ref().children.get()).push(SendPtr::from(self)),
+            None => roots.push(SendPtr::from(self)),
+        }
+    }
+
+    /// Unlinks this frame from wherever [`Frame::register`] placed it.
+    unsafe fn unregister(&self) {
+        let mut roots = LIVE_TREE.lock().unwrap();
+        let siblings = match self.parent {
+            Some(parent) => &mut *parent.as_ref().children.get(),
+            None => &mut *roots,
+        };
+        if let Some(index) = siblings.iter().position(|&frame| frame == SendPtr::from(self)) {
+            siblings.swap_remove(index);
+        }
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        // SAFETY: `register` was called with this same frame before it could have been linked
+        // into any other frame's `children`, so it's safe to unlink here.
+        unsafe { self.unregister() }
+    }
 }
 
 /// The ambiant backtracing context.
@@ -69,6 +191,9 @@ pub(crate) struct Context {
     /// The collection of backtraces collected beneath the invocation of
     /// [`Trace::root`].
     trace: Cell<Option<Trace>>,
+    /// The [`CaptureOptions`] governing the active [`Trace::capture`]/[`Trace::capture_with`], if
+    /// any. `None` outside of a capture.
+    options: Cell<Option<CaptureOptions>>,
 }
 
 impl Context {
@@ -78,10 +203,11 @@ impl Context {
     {
         std::thread_local! {
             #[allow(clippy::declare_interior_mutable_const)]
-            static CONTEXT: Context = const { 
+            static CONTEXT: Context = const {
                 Context {
                     active_frame: Cell::new(None),
                     trace: Cell::new(None),
+                    options: Cell::new(None),
                 }
             };
         }
@@ -101,6 +227,13 @@ impl Context {
     {
         unsafe { Self::with_current(|context| f(&context.trace)) }
     }
+
+    pub(crate) fn with_current_options<F, R>(f: F) -> R
+    where
+        F: FnOnce(&Cell<Option<CaptureOptions>>) -> R,
+    {
+        unsafe { Self::with_current(|context| f(&context.options)) }
+    }
 }
 
 
@@ -113,14 +246,27 @@ impl Context {
 pub struct Trace {
     // The linear backtraces that comprise this trace. These linear traces can
     // be re-knitted into a tree.
-    backtraces: Vec<Backtrace>,
+    backtraces: Vec<RawTrace>,
 }
 
 impl Trace {
     /// Invokes `f`, returning both its result and the collection of backtraces
     /// captured at each sub-invocation of [`Trace::leaf`].
+    ///
+    /// Capture is governed by the process-wide default [`CaptureOptions`] — see its docs for how
+    /// that default is chosen; use [`Trace::capture_with`] to override it for just this capture.
     #[inline(never)]
     pub fn capture<F, R>(f: F) -> (R, Trace)
+    where
+        F: FnOnce() -> R,
+    {
+        Self::capture_with(default_options().clone(), f)
+    }
+
+    /// Like [`Trace::capture`], but with an explicit [`CaptureOptions`] rather than the
+    /// process-wide default.
+    #[inline(never)]
+    pub fn capture_with<F, R>(options: CaptureOptions, f: F) -> (R, Trace)
     where
         F: FnOnce() -> R,
     {
@@ -128,15 +274,19 @@ impl Trace {
             backtraces: vec![],
         };
 
-        let previous = Context::with_current_collector(|current| {
+        let previous_trace = Context::with_current_collector(|current| {
             current.replace(Some(collector))
         });
+        let previous_options = Context::with_current_options(|current| {
+            current.replace(Some(options))
+        });
 
         let result = Trace::root(f);
 
+        Context::with_current_options(|current| current.set(previous_options));
         let collector =
             Context::with_current_collector(|current| {
-                current.replace(previous)
+                current.replace(previous_trace)
             }).unwrap();
 
         (result, collector)
@@ -151,6 +301,7 @@ impl Trace {
             let mut frame = Frame {
                 inner_addr: Self::root::<F, R> as *const c_void,
                 parent: None,
+                children: UnsafeCell::new(Vec::new()),
             };
 
             Context::with_current_frame(|current| {
@@ -158,6 +309,8 @@ impl Trace {
                 current.set(Some(NonNull::from(&frame)));
             });
 
+            frame.register();
+
             let _restore = crate::defer(|| {
                 Context::with_current_frame(|current| {
                     current.set(frame.parent);
@@ -168,50 +321,171 @@ impl Trace {
         }
     }
 
+    /// Like [`Trace::root`], but for a [`Future`](std::future::Future) rather than a synchronous
+    /// closure.
+    ///
+    /// The returned [`Traced`] future re-establishes itself as the unwinding root on every poll,
+    /// so that [`trace_leaf`] calls made anywhere inside it — including from futures it polls in
+    /// turn — are attributed to it.
+    pub fn root_future<F>(future: F) -> Traced<F> {
+        Traced::new(future)
+    }
+
+    /// Like [`Trace::capture`], but for a [`Future`](std::future::Future) rather than a
+    /// synchronous closure.
+    pub fn capture_future<F>(future: F) -> CaptureFuture<F> {
+        CaptureFuture {
+            traced: Traced::new(future),
+            trace: Trace { backtraces: vec![] },
+        }
+    }
+
+    /// Returns a structured, walkable view of this trace's tree, for programmatic consumers that
+    /// want to traverse, filter, or re-render it rather than scrape [`Display`](fmt::Display)'s
+    /// box-drawing output.
+    pub fn tree(&self) -> TraceTree {
+        TraceTree(Tree::from_trace(self.clone()))
+    }
+
+    /// Like [`Trace::tree`], but collapses sibling subtrees that are structurally identical down
+    /// to the leaves, annotating the merged node with a multiplicity count.
+    ///
+    /// Useful when dumping large numbers of leaves that share long common prefixes — for example,
+    /// hundreds of tasks parked at the same few `.await` points — where the unaggregated tree
+    /// would otherwise repeat the same subtree hundreds of times.
+    pub fn aggregate(&self) -> TraceTree {
+        let mut tree = Tree::from_trace(self.clone());
+        tree.aggregate();
+        TraceTree(tree)
+    }
+
+    /// Force symbol resolution on every backtrace this trace holds, rather than leaving it until
+    /// the trace is walked or formatted.
+    ///
+    /// Resolution borrows from the process's own debug info, which may not be available once a
+    /// trace outlives the context it was captured in — for example, after crossing a thread
+    /// boundary or being serialized for later inspection. Call this first to pay that cost while
+    /// the debug info is still at hand.
+    pub fn resolve(&mut self) {
+        for raw in &mut self.backtraces {
+            let resolved = symbolize(std::mem::replace(raw, RawTrace::Resolved(vec![])));
+            *raw = RawTrace::Resolved(resolved);
+        }
+    }
+
+    /// Snapshot every [`Trace::root`]/[`Traced`](crate::Traced) invocation currently live on any
+    /// thread, without unwinding any stack.
+    ///
+    /// Unlike [`Trace::capture`], which only sees what [`Trace::leaf`] records while its root is
+    /// on the calling thread's stack, this walks the intrusive tree of live [`Frame`]s directly —
+    /// so it can be called from anywhere, including a signal handler or an admin endpoint
+    /// inspecting another thread. Its resolution is coarser than an unwound backtrace: it records
+    /// one address per live root invocation or [`Traced`] future, not every intervening stack
+    /// frame.
+    pub fn snapshot_active() -> Trace {
+        let roots = LIVE_TREE.lock().unwrap();
+        let mut backtraces = vec![];
+        for &root in roots.iter() {
+            unsafe { walk_live(root.0, &mut vec![], &mut backtraces) };
+        }
+        Trace { backtraces }
+    }
+
     /// If this is a sub-invocation of [`Trace::root`], capture a backtrace.
     ///
     /// The captured backtrace will be returned by [`Trace::root`].
     ///
     /// Invoking this function does nothing when it is not a sub-invocation
     /// [`Trace::root`].
+    ///
+    /// Governed by the enclosing [`Trace::capture`]/[`Trace::capture_with`]'s [`CaptureOptions`]:
+    /// if capture is disabled, this returns before allocating anything; otherwise, unwinding stops
+    /// early past any configured max depth, and frames matching a configured exclude prefix are
+    /// dropped before they're ever resolved into the tree.
     // This function is marked `#[inline(never)]` to ensure that it gets a distinct `Frame` in the
     // backtrace, below which frames should not be included in the backtrace (since they reflect the
     // internal implementation details of this crate).
     #[inline(never)]
     pub fn leaf() {
-        unsafe {
-        Context::with_current(|context_cell| {
-            if let Some(mut collector) = context_cell.trace.take() {
-                let mut frames = vec![];
-                let mut above_leaf = false;
- 
-                if let Some(active_frame) = context_cell.active_frame.get() {
-                    let active_frame = active_frame.as_ref();
-
-                    backtrace::trace(|frame| {
-                        println!("boom!");
-                        let below_root = !ptr::eq(frame.symbol_address(), active_frame.inner_addr);
-
-                        // only capture frames above `Trace::leaf()` and below
-                        // `Trace::root_inner()`.
-                        if dbg!(above_leaf) && dbg!(below_root) {
-                            frames.push(frame.to_owned().into());
+        // SAFETY: `Self::leaf` is the function currently executing.
+        unsafe { capture_leaf(Self::leaf as *const c_void) }
+    }
+}
+
+/// Shared by [`Trace::leaf`] and [`trace_leaf`](crate::trace_leaf): captures a backtrace from
+/// directly above `boundary` down to the nearest enclosing [`Trace::root`]/[`Traced`], governed by
+/// the enclosing [`Trace::capture`]/[`Trace::capture_with`]'s [`CaptureOptions`] exactly as
+/// [`Trace::leaf`] documents.
+///
+/// `boundary` is the address of whichever public function the caller entered through, so that
+/// function's own frame — not just its caller's — is excluded from the capture, leaving the first
+/// captured frame as the caller's.
+///
+/// # Safety
+///
+/// `boundary` must be the address of the function currently executing.
+#[inline(never)]
+pub(crate) unsafe fn capture_leaf(boundary: *const c_void) {
+    Context::with_current(|context_cell| {
+        let options = context_cell.options.take();
+        let enabled = options
+            .as_ref()
+            .map_or_else(|| default_options().is_enabled(), CaptureOptions::is_enabled);
+
+        if !enabled {
+            context_cell.options.set(options);
+            return;
+        }
+
+        if let Some(mut collector) = context_cell.trace.take() {
+            let max_depth = options
+                .as_ref()
+                .and_then(CaptureOptions::max_depth)
+                .unwrap_or_else(|| default_options().max_depth().unwrap_or(usize::MAX));
+
+            let mut frames = vec![];
+            let mut above_leaf = false;
+            let mut depth = 0;
+
+            if let Some(active_frame) = context_cell.active_frame.get() {
+                let active_frame = active_frame.as_ref();
+
+                backtrace::trace(|frame| {
+                    let below_root =
+                        !ptr::eq(frame.symbol_address(), active_frame.inner_addr);
+
+                    // only capture frames above `boundary` and below `Trace::root`.
+                    if above_leaf && below_root {
+                        if depth >= max_depth {
+                            return false;
                         }
 
-                        if ptr::eq(frame.symbol_address(), Self::leaf as *const _) {
-                            above_leaf = true;
+                        let mut owned: BacktraceFrame = frame.to_owned().into();
+                        let excluded = match options.as_ref() {
+                            Some(options) => options.excludes(&mut owned),
+                            None => default_options().excludes(&mut owned),
+                        };
+
+                        if !excluded {
+                            frames.push(owned);
+                            depth += 1;
                         }
+                    }
 
-                        // only continue unwinding if we're below `Trace::root`
-                        dbg!(below_root)
-                    });
-                }
-                collector.backtraces.push(frames);
-                context_cell.trace.set(Some(collector));
+                    if ptr::eq(frame.symbol_address(), boundary) {
+                        above_leaf = true;
+                    }
+
+                    // only continue unwinding if we're below `Trace::root`
+                    below_root
+                });
             }
-        });
+            collector.backtraces.push(RawTrace::Unwound(frames));
+            context_cell.trace.set(Some(collector));
         }
-    }
+
+        context_cell.options.set(options);
+    });
 }
 
 impl fmt::Display for Trace {
@@ -220,6 +494,66 @@ impl fmt::Display for Trace {
     }
 }
 
+/// Depth-first walk of the live [`Frame`] tree rooted at `frame`, recording one [`RawTrace::Live`]
+/// per leaf (a frame with no currently-registered children) as the chain of addresses from `frame`
+/// down to that leaf.
+///
+/// # Safety
+///
+/// The caller must hold `LIVE_TREE`'s lock for the duration of the walk.
+unsafe fn walk_live(frame: NonNull<Frame>, path: &mut Vec<*const c_void>, out: &mut Vec<RawTrace>) {
+    path.push(frame.as_ref().inner_addr);
+
+    let children = &*frame.as_ref().children.get();
+    if children.is_empty() {
+        out.push(RawTrace::Live(path.clone()));
+    } else {
+        for &child in children {
+            walk_live(child.0, path, out);
+        }
+    }
+
+    path.pop();
+}
+
+/// Captures a raw, unscoped backtrace from directly above this call down to the bottom of the
+/// stack — unlike [`Trace::leaf`], this doesn't require an enclosing [`Trace::root`], so it can be
+/// called from anywhere. Used by [`TracedError`] to attach a trace to an error at the point it's
+/// constructed.
+#[inline(never)]
+pub(crate) fn capture_here(options: &CaptureOptions) -> Backtrace {
+    if !options.is_enabled() {
+        return vec![];
+    }
+
+    let max_depth = options.max_depth().unwrap_or(usize::MAX);
+    let mut frames = vec![];
+    let mut above_here = false;
+    let mut depth = 0;
+
+    backtrace::trace(|frame| {
+        if above_here {
+            if depth >= max_depth {
+                return false;
+            }
+
+            let mut owned: BacktraceFrame = frame.to_owned().into();
+            if !options.excludes(&mut owned) {
+                frames.push(owned);
+                depth += 1;
+            }
+        }
+
+        if ptr::eq(frame.symbol_address(), capture_here as *const _) {
+            above_here = true;
+        }
+
+        true
+    });
+
+    frames
+}
+
 fn defer<F: FnOnce() -> R, R>(f: F) -> impl Drop {
     use std::mem::ManuallyDrop;
 
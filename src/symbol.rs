@@ -0,0 +1,88 @@
+use std::{ffi::c_void, fmt, path::PathBuf};
+
+/// A symbolicated location within a captured backtrace.
+///
+/// Resolution (turning a raw instruction pointer into a name, file, line,
+/// and column) is deferred past capture: it happens when a [`crate::Trace`]
+/// is re-knit into a [`crate::tree::Tree`], each time it's walked or
+/// formatted, unless [`crate::Trace::resolve`] has already done it. A capture
+/// with exclude prefixes resolves each frame as it's captured, to match its
+/// name against them.
+#[derive(Clone, PartialEq, Hash)]
+pub(crate) struct Symbol {
+    name: Option<String>,
+    filename: Option<PathBuf>,
+    lineno: Option<u32>,
+    colno: Option<u32>,
+}
+
+impl Symbol {
+    pub(crate) fn new(symbol: &backtrace::BacktraceSymbol) -> Self {
+        Self {
+            name: symbol.name().map(|name| name.to_string()),
+            filename: symbol.filename().map(ToOwned::to_owned),
+            lineno: symbol.lineno(),
+            colno: symbol.colno(),
+        }
+    }
+
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn file(&self) -> Option<&std::path::Path> {
+        self.filename.as_deref()
+    }
+
+    pub(crate) fn line(&self) -> Option<u32> {
+        self.lineno
+    }
+
+    pub(crate) fn column(&self) -> Option<u32> {
+        self.colno
+    }
+
+    /// Resolve a raw address directly, bypassing stack unwinding entirely.
+    ///
+    /// Used to symbolize the addresses recorded in the live [`crate::Frame`] tree by
+    /// [`crate::Trace::snapshot_active`] — each of which is a function's literal entry address
+    /// (a bare `fn` pointer), not a call-site return address. Some platforms' debug info doesn't
+    /// have a line-table row exactly at a function's first byte, so the address is nudged forward
+    /// by one before resolving; this still lands inside the same function's prologue. A single
+    /// address may resolve to more than one `Symbol`, innermost-first, when it corresponds to an
+    /// inlined call chain — mirroring how [`Symbol::new`] handles an unwound frame.
+    pub(crate) fn resolve(addr: *const c_void) -> Vec<Self> {
+        let addr = (addr as usize).wrapping_add(1) as *mut c_void;
+        let mut symbols = vec![];
+        backtrace::resolve(addr, |symbol| {
+            symbols.push(Symbol {
+                name: symbol.name().map(|name| name.to_string()),
+                filename: symbol.filename().map(ToOwned::to_owned),
+                lineno: symbol.lineno(),
+                colno: symbol.colno(),
+            });
+        });
+        symbols
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name}")?,
+            None => write!(f, "<unknown>")?,
+        }
+
+        if let Some(filename) = &self.filename {
+            write!(f, " at {}", filename.display())?;
+            if let Some(lineno) = self.lineno {
+                write!(f, ":{lineno}")?;
+                if let Some(colno) = self.colno {
+                    write!(f, ":{colno}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+use crate::{RawTrace, Symbol, Trace};
+
+/// A tree of [`Symbol`]s, re-knit from the linear backtraces collected by a
+/// [`Trace`].
+#[derive(Clone, Default)]
+pub(crate) struct Tree {
+    pub(crate) roots: Vec<Node>,
+}
+
+/// A single, resolved node in a captured [`Trace`]'s tree — see [`Trace::tree`].
+///
+/// [`Trace::tree`]: crate::Trace::tree
+#[derive(Clone)]
+pub struct Node {
+    pub(crate) symbol: Symbol,
+    pub(crate) children: Vec<Node>,
+    /// How many structurally-identical sibling subtrees this node stands in for. `1` unless this
+    /// tree has been through [`Trace::aggregate`](crate::Trace::aggregate).
+    pub(crate) count: usize,
+}
+
+impl Node {
+    /// The resolved name of the function this node represents, if it could be resolved.
+    pub fn name(&self) -> Option<&str> {
+        self.symbol.name()
+    }
+
+    /// The source file this node was captured at, if known.
+    pub fn file(&self) -> Option<&Path> {
+        self.symbol.file()
+    }
+
+    /// The source line this node was captured at, if known.
+    pub fn line(&self) -> Option<u32> {
+        self.symbol.line()
+    }
+
+    /// The source column this node was captured at, if known.
+    pub fn column(&self) -> Option<u32> {
+        self.symbol.column()
+    }
+
+    /// How many structurally-identical sibling subtrees this node stands in for — `1` unless this
+    /// tree has been through [`Trace::aggregate`](crate::Trace::aggregate), in which case it's the
+    /// number of siblings that were collapsed into this one.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The nodes directly beneath this one in the tree.
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// A hash of this node's symbol, count, and, recursively, its children — two subtrees with the
+    /// same fingerprint render identically, *including* multiplicity: two otherwise-identical
+    /// subtrees whose children were collapsed to different counts get different fingerprints, so
+    /// [`merge_siblings`] won't fold one's counts into the other and silently lose them.
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash_into(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_into<H: Hasher>(&self, hasher: &mut H) {
+        self.symbol.hash(hasher);
+        self.count.hash(hasher);
+        for child in &self.children {
+            child.hash_into(hasher);
+        }
+    }
+
+    fn aggregate(&mut self) {
+        for child in &mut self.children {
+            child.aggregate();
+        }
+        merge_siblings(&mut self.children);
+    }
+}
+
+/// Merge adjacent siblings with identical fingerprints, folding their counts together. Siblings
+/// are sorted by fingerprint first so that equal subtrees are guaranteed to be adjacent, which
+/// leaves siblings in fingerprint order rather than capture order; `DefaultHasher::new` uses fixed
+/// keys, so that order is the same from run to run.
+fn merge_siblings(siblings: &mut Vec<Node>) {
+    siblings.sort_by_key(Node::fingerprint);
+
+    let mut merged: Vec<Node> = Vec::with_capacity(siblings.len());
+    for node in siblings.drain(..) {
+        match merged.last_mut() {
+            Some(last) if last.fingerprint() == node.fingerprint() => {
+                last.count += node.count;
+            }
+            _ => merged.push(node),
+        }
+    }
+    *siblings = merged;
+}
+
+/// A structured, walkable view of a [`Trace`](crate::Trace)'s tree of captured backtraces.
+///
+/// Returned by [`Trace::tree`](crate::Trace::tree). Unlike [`Trace`](crate::Trace)'s
+/// [`Display`](fmt::Display) impl, which renders the tree directly to a formatter, this exposes
+/// each [`Node`]'s resolved name, file, line, and column, so the tree can be traversed, filtered,
+/// or re-rendered programmatically.
+#[derive(Clone)]
+pub struct TraceTree(pub(crate) Tree);
+
+impl TraceTree {
+    /// The top-level nodes of the tree — one per distinct symbol (name, file, line, and column)
+    /// at the outermost end of the trace's backtraces. For a [`Trace::capture`](crate::Trace::capture),
+    /// these are the locations in the captured closure from which leaves were reached, so a single
+    /// capture can yield several roots.
+    pub fn roots(&self) -> &[Node] {
+        &self.0.roots
+    }
+}
+
+impl fmt::Display for TraceTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Tree {
+    /// Re-knit the linear backtraces collected by `trace` into a tree,
+    /// merging backtraces that share a common prefix.
+    pub(crate) fn from_trace(trace: Trace) -> Self {
+        let mut tree = Tree::default();
+        for backtrace in trace.backtraces {
+            insert(&mut tree.roots, symbolize(backtrace));
+        }
+        tree
+    }
+
+    /// Collapse sibling subtrees that are structurally identical down to the leaves, folding
+    /// each group into one node that carries a multiplicity [`count`](Node::count).
+    pub(crate) fn aggregate(&mut self) {
+        for root in &mut self.roots {
+            root.aggregate();
+        }
+        merge_siblings(&mut self.roots);
+    }
+}
+
+fn insert(siblings: &mut Vec<Node>, mut symbols: Vec<Symbol>) {
+    if symbols.is_empty() {
+        return;
+    }
+    let symbol = symbols.remove(0);
+    match siblings.iter_mut().find(|node| node.symbol == symbol) {
+        Some(node) => insert(&mut node.children, symbols),
+        None => {
+            let mut node = Node {
+                symbol,
+                children: vec![],
+                count: 1,
+            };
+            insert(&mut node.children, symbols);
+            siblings.push(node);
+        }
+    }
+}
+
+/// Resolve a captured [`RawTrace`] into a root-first list of symbols.
+///
+/// For [`RawTrace::Unwound`], a single [`backtrace::BacktraceFrame`] may resolve to more than one
+/// [`Symbol`] when the frame's address corresponds to an inlined call chain; those symbols are
+/// innermost-first, so they're reversed along with the frames to keep the whole list in a
+/// consistent root-to-leaf order. [`RawTrace::Live`] is resolved the same way, address by address,
+/// except its addresses are already in root-to-leaf order. [`RawTrace::Resolved`] has already paid
+/// this cost (via [`Trace::resolve`](crate::Trace::resolve)), so it's returned as-is.
+pub(crate) fn symbolize(raw: RawTrace) -> Vec<Symbol> {
+    match raw {
+        RawTrace::Unwound(backtrace) => backtrace
+            .into_iter()
+            .rev()
+            .flat_map(|mut frame| {
+                frame.resolve();
+                frame
+                    .symbols()
+                    .iter()
+                    .rev()
+                    .map(Symbol::new)
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        RawTrace::Live(addrs) => addrs
+            .into_iter()
+            .flat_map(|addr| {
+                let mut symbols = Symbol::resolve(addr);
+                symbols.reverse();
+                symbols
+            })
+            .collect(),
+        RawTrace::Resolved(symbols) => symbols,
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for root in &self.roots {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            write!(f, "╼ ")?;
+            fmt_node(f, root)?;
+            fmt_children(f, &root.children, "  ")?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_node(f: &mut fmt::Formatter<'_>, node: &Node) -> fmt::Result {
+    write!(f, "{}", node.symbol)?;
+    if node.count > 1 {
+        write!(f, " (×{})", node.count)?;
+    }
+    Ok(())
+}
+
+fn fmt_children(f: &mut fmt::Formatter<'_>, children: &[Node], prefix: &str) -> fmt::Result {
+    let mut children = children.iter().peekable();
+    while let Some(child) = children.next() {
+        let is_last = children.peek().is_none();
+        let connector = if is_last { "└╼ " } else { "├╼ " };
+        write!(f, "\n{prefix}{connector}")?;
+        fmt_node(f, child)?;
+
+        let child_prefix = format!("{prefix}{}", if is_last { "   " } else { "│  " });
+        fmt_children(f, &child.children, &child_prefix)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use scoped_trace::Trace;
+
+fn call_site_a() {
+    shared();
+}
+
+fn call_site_b() {
+    shared();
+}
+
+#[inline(never)]
+fn shared() {
+    Trace::leaf();
+}
+
+/// `call_site_a` and `call_site_b` both call `shared`, but they're distinct call sites, so their
+/// subtrees must stay distinct roots with their own counts — collapsing them (or losing either
+/// count) would misreport which site accounts for which share of the captured traces.
+#[allow(clippy::redundant_closure)]
+#[test]
+fn aggregate_does_not_merge_distinct_call_sites() {
+    let (_, trace) = Trace::capture(|| {
+        call_site_a();
+        call_site_b();
+    });
+
+    let tree = trace.aggregate();
+    let roots = tree.roots();
+
+    assert_eq!(roots.len(), 2, "distinct call sites must not be merged");
+    for root in roots {
+        assert_eq!(root.count(), 1);
+    }
+}
@@ -0,0 +1,44 @@
+use scoped_trace::{CaptureOptions, Trace};
+
+#[inline(never)]
+fn level1() {
+    level2();
+}
+
+#[inline(never)]
+fn level2() {
+    Trace::leaf();
+}
+
+/// `CaptureOptions::disabled()` should make `Trace::leaf()` a no-op: no backtrace recorded at all,
+/// not just an empty one.
+#[allow(clippy::redundant_closure)]
+#[test]
+fn disabled_capture_records_nothing() {
+    let (_, trace) = Trace::capture_with(CaptureOptions::disabled(), || level1());
+    assert_eq!(format!("{trace}"), "");
+}
+
+/// With `max_depth(1)`, only the frame directly above `Trace::leaf` should survive — unwinding
+/// should stop there rather than continuing up through `level1` to the capturing root.
+#[allow(clippy::redundant_closure)]
+#[test]
+fn max_depth_limits_how_far_a_capture_unwinds() {
+    let (_, trace) = Trace::capture_with(CaptureOptions::new().with_max_depth(1), || level1());
+
+    let tree = trace.tree();
+    let roots = tree.roots();
+    assert_eq!(roots.len(), 1);
+    assert!(roots[0].name().unwrap().contains("level2"), "{:?}", roots[0].name());
+    assert!(roots[0].children().is_empty());
+}
+
+/// Every frame captured in this test belongs to this module, so excluding its own prefix should
+/// drop every frame and leave nothing behind.
+#[allow(clippy::redundant_closure)]
+#[test]
+fn exclude_drops_frames_by_name_prefix() {
+    let options = CaptureOptions::new().exclude(module_path!());
+    let (_, trace) = Trace::capture_with(options, || level1());
+    assert_eq!(format!("{trace}"), "");
+}
@@ -0,0 +1,21 @@
+use scoped_trace::Trace;
+
+#[inline(never)]
+fn leaf() {
+    Trace::leaf();
+}
+
+/// `SCOPED_TRACE=off` should disable the process-wide default capture, exactly like handing
+/// `CaptureOptions::disabled()` to `Trace::capture_with` explicitly — mirroring
+/// `RUST_BACKTRACE=0`'s effect on `std::backtrace::Backtrace`. Kept in its own process, since the
+/// default is cached for the life of the process the first time it's read.
+#[allow(clippy::redundant_closure)]
+#[test]
+fn scoped_trace_env_var_disables_the_default_capture() {
+    // SAFETY: this is the only test in this binary, and nothing else reads the process
+    // environment before `Trace::capture` caches the default below.
+    unsafe { std::env::set_var("SCOPED_TRACE", "off") };
+
+    let (_, trace) = Trace::capture(|| leaf());
+    assert_eq!(format!("{trace}"), "");
+}
@@ -0,0 +1,47 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use scoped_trace::Trace;
+
+struct Parked;
+
+impl Future for Parked {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        Poll::Pending
+    }
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn no_op(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+}
+
+/// `Parked` never completes, so after its first poll returns `Pending` the task is suspended with
+/// no stack to unwind — exactly the case `Trace::leaf` can't see. `Trace::snapshot_active` should
+/// still find it by walking the live frame tree, and should stop finding it once the `Traced`
+/// future wrapping it is dropped.
+#[test]
+fn snapshot_active_sees_a_parked_task() {
+    let mut future = Box::pin(Trace::root_future(Parked));
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(matches!(future.as_mut().poll(&mut cx), Poll::Pending));
+
+    let rendered = format!("{}", Trace::snapshot_active());
+    assert!(rendered.contains("Traced"), "{rendered}");
+
+    drop(future);
+
+    let rendered = format!("{}", Trace::snapshot_active());
+    assert_eq!(rendered, "", "frame should unregister once the Traced future is dropped");
+}
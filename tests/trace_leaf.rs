@@ -0,0 +1,48 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use scoped_trace::{trace_leaf, Trace};
+
+struct Leaf;
+
+impl Future for Leaf {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        call_trace_leaf(cx)
+    }
+}
+
+#[inline(never)]
+fn call_trace_leaf(cx: &mut Context<'_>) -> Poll<()> {
+    trace_leaf(cx)
+}
+
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn no_op(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+}
+
+/// `call_trace_leaf` is the caller of `trace_leaf`, so it should be the first frame in the
+/// recorded trace — `trace_leaf`'s own frame must not show up above it.
+#[test]
+fn trace_leaf_excludes_its_own_frame() {
+    let mut future = Box::pin(Trace::capture_future(Leaf));
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let (_, trace) = match future.as_mut().poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("Leaf is always ready after its first poll"),
+    };
+
+    let rendered = format!("{trace}");
+    assert!(rendered.contains("call_trace_leaf"), "{rendered}");
+    assert!(!rendered.contains("future::trace_leaf"), "{rendered}");
+}
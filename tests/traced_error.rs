@@ -0,0 +1,45 @@
+use std::fmt;
+
+use scoped_trace::{CaptureOptions, TraceError};
+
+#[derive(Debug)]
+struct MyError;
+
+impl fmt::Display for MyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "something went wrong")
+    }
+}
+
+impl std::error::Error for MyError {}
+
+#[inline(never)]
+fn fails() -> Result<(), MyError> {
+    Err(MyError)
+}
+
+/// `TraceError::traced` should capture a backtrace at the point the error is constructed, and
+/// `Display` should render it beneath the error's own message.
+#[test]
+fn trace_error_captures_a_backtrace_at_the_call_site() {
+    let traced = fails().unwrap_err().traced();
+
+    assert!(matches!(traced.inner(), MyError));
+
+    let rendered = format!("{traced}");
+    assert!(rendered.starts_with("something went wrong\n"), "{rendered}");
+    assert!(
+        rendered.contains("trace_error_captures_a_backtrace_at_the_call_site"),
+        "{rendered}"
+    );
+
+    assert!(matches!(traced.into_inner(), MyError));
+}
+
+/// `CaptureOptions::disabled()` passed via `with_trace`/`TracedError::with_options` should
+/// suppress the captured trace entirely, same as it does for `Trace::capture_with`.
+#[test]
+fn with_trace_honors_an_explicit_capture_options() {
+    let traced = fails().unwrap_err().with_trace(CaptureOptions::disabled());
+    assert_eq!(format!("{}", traced.trace()), "");
+}
@@ -0,0 +1,38 @@
+use scoped_trace::Trace;
+
+fn foo() {
+    bar();
+    baz();
+}
+
+#[inline(always)]
+fn bar() {
+    Trace::leaf();
+}
+
+#[inline(always)]
+fn baz() {
+    Trace::leaf();
+}
+
+#[allow(clippy::redundant_closure)]
+#[test]
+fn tree_mirrors_display() {
+    let (_, trace) = Trace::capture(|| foo());
+    let tree = trace.tree();
+
+    let roots = tree.roots();
+    assert_eq!(roots.len(), 1);
+
+    let closure = &roots[0];
+    assert!(closure.name().unwrap().contains("tree_mirrors_display"));
+    assert_eq!(closure.children().len(), 2);
+
+    let bar = closure.children()[0].children();
+    assert_eq!(bar.len(), 1);
+    assert!(bar[0].name().unwrap().contains("bar"));
+
+    let baz = closure.children()[1].children();
+    assert_eq!(baz.len(), 1);
+    assert!(baz[0].name().unwrap().contains("baz"));
+}